@@ -1,13 +1,15 @@
+use std::collections::hash_map::RandomState;
 use std::collections::{VecDeque, HashSet};
-use std::hash::Hash;
+use std::hash::{BuildHasher, Hash};
+use std::ops::{BitOr, Sub};
 
 #[derive(Default)]
-pub struct UnvisitedIterator<T: Eq + Hash + Clone> {
-    visited: HashSet<T>,
+pub struct UnvisitedIterator<T: Eq + Hash + Clone, S: BuildHasher = RandomState> {
+    visited: HashSet<T, S>,
     inner: VecDeque<T>,
 }
 
-impl<T: Eq + Hash + Clone> UnvisitedIterator<T> {
+impl<T: Eq + Hash + Clone> UnvisitedIterator<T, RandomState> {
     pub fn from_value(value: T) -> Self {
         let mut inner = VecDeque::new();
         inner.push_front(value);
@@ -24,6 +26,32 @@ impl<T: Eq + Hash + Clone> UnvisitedIterator<T> {
             inner: iter.collect::<Vec<_>>().into(),
         }
     }
+}
+
+impl<T: Eq + Hash + Clone, S: BuildHasher> UnvisitedIterator<T, S> {
+    pub fn with_hasher(value: T, hasher: S) -> Self {
+        let mut inner = VecDeque::new();
+        inner.push_front(value);
+
+        Self {
+            visited: HashSet::with_hasher(hasher),
+            inner,
+        }
+    }
+
+    pub fn from_iter_with_hasher<Iter: Iterator<Item = T>>(iter: Iter, hasher: S) -> Self {
+        Self {
+            visited: HashSet::with_hasher(hasher),
+            inner: iter.collect::<Vec<_>>().into(),
+        }
+    }
+
+    pub fn with_visited<Iter: Iterator<Item = T>>(visited: HashSet<T, S>, iter: Iter) -> Self {
+        Self {
+            visited,
+            inner: iter.collect::<Vec<_>>().into(),
+        }
+    }
 
     pub fn push_front(&mut self, value: T) {
         self.inner.push_front(value);
@@ -32,9 +60,49 @@ impl<T: Eq + Hash + Clone> UnvisitedIterator<T> {
     pub fn push_back(&mut self, value: T) {
         self.inner.push_back(value);
     }
+
+    pub fn visited(&self) -> &HashSet<T, S> {
+        &self.visited
+    }
+
+    pub fn into_parts(self) -> (HashSet<T, S>, VecDeque<T>) {
+        (self.visited, self.inner)
+    }
+
+    pub fn into_visited(mut self) -> HashSet<T, S> {
+        for value in self.inner.drain(..) {
+            self.visited.insert(value);
+        }
+
+        self.visited
+    }
 }
 
-impl<T: Eq + Hash + Clone> Iterator for UnvisitedIterator<T> {
+impl<T, S> BitOr for &UnvisitedIterator<T, S>
+where
+    T: Eq + Hash + Clone,
+    S: BuildHasher + Default,
+{
+    type Output = HashSet<T, S>;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        &self.visited | &rhs.visited
+    }
+}
+
+impl<T, S> Sub for &UnvisitedIterator<T, S>
+where
+    T: Eq + Hash + Clone,
+    S: BuildHasher + Default,
+{
+    type Output = HashSet<T, S>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        &self.visited - &rhs.visited
+    }
+}
+
+impl<T: Eq + Hash + Clone, S: BuildHasher> Iterator for UnvisitedIterator<T, S> {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -49,8 +117,12 @@ impl<T: Eq + Hash + Clone> Iterator for UnvisitedIterator<T> {
 
         None
     }
-}
 
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Dedup can only shrink the stream, never grow it.
+        (0, Some(self.inner.len()))
+    }
+}
 
 pub trait IntoUnvisitedIterator<T: Eq + Hash + Clone> {
     fn skip_visited(self) -> UnvisitedIterator<T>;
@@ -65,6 +137,246 @@ impl<T: Eq + Hash + Clone, Iter: Iterator<Item = T>> IntoUnvisitedIterator<T> fo
     }
 }
 
+pub struct UnvisitedByKeyIterator<T, K, F, S = RandomState>
+where
+    K: Eq + Hash + Clone,
+    F: FnMut(&T) -> &K,
+    S: BuildHasher,
+{
+    visited: HashSet<K, S>,
+    inner: VecDeque<T>,
+    key_fn: F,
+}
+
+impl<T, K, F> UnvisitedByKeyIterator<T, K, F, RandomState>
+where
+    K: Eq + Hash + Clone,
+    F: FnMut(&T) -> &K,
+{
+    pub fn from_iter<Iter: Iterator<Item = T>>(iter: Iter, key_fn: F) -> Self {
+        Self {
+            visited: HashSet::new(),
+            inner: iter.collect::<Vec<_>>().into(),
+            key_fn,
+        }
+    }
+}
+
+impl<T, K, F, S> UnvisitedByKeyIterator<T, K, F, S>
+where
+    K: Eq + Hash + Clone,
+    F: FnMut(&T) -> &K,
+    S: BuildHasher,
+{
+    pub fn push_front(&mut self, value: T) {
+        self.inner.push_front(value);
+    }
+
+    pub fn push_back(&mut self, value: T) {
+        self.inner.push_back(value);
+    }
+}
+
+impl<T, K, F, S> Iterator for UnvisitedByKeyIterator<T, K, F, S>
+where
+    K: Eq + Hash + Clone,
+    F: FnMut(&T) -> &K,
+    S: BuildHasher,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(next) = self.inner.pop_front() {
+            let key = (self.key_fn)(&next).clone();
+            if self.visited.contains(&key) {
+                continue;
+            }
+
+            self.visited.insert(key);
+            return Some(next);
+        }
+
+        None
+    }
+}
+
+pub trait IntoUnvisitedByKeyIterator<T> {
+    fn skip_visited_by_key<K, F>(self, key_fn: F) -> UnvisitedByKeyIterator<T, K, F, RandomState>
+    where
+        K: Eq + Hash + Clone,
+        F: FnMut(&T) -> &K;
+}
+
+impl<T, Iter: Iterator<Item = T>> IntoUnvisitedByKeyIterator<T> for Iter {
+    fn skip_visited_by_key<K, F>(self, key_fn: F) -> UnvisitedByKeyIterator<T, K, F, RandomState>
+    where
+        K: Eq + Hash + Clone,
+        F: FnMut(&T) -> &K,
+    {
+        UnvisitedByKeyIterator::from_iter(self, key_fn)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraversalOrder {
+    Bfs,
+    Dfs,
+}
+
+pub struct Traversal<T, N, F>
+where
+    T: Eq + Hash + Clone,
+    N: IntoIterator<Item = T>,
+    F: FnMut(&T) -> N,
+{
+    inner: UnvisitedIterator<T>,
+    order: TraversalOrder,
+    successors: F,
+    // Every node ever pushed into `inner`'s frontier (entries are never removed), tracked
+    // separately from `visited` so a node with high fan-in isn't pushed once per predecessor
+    // before it's popped.
+    queued: HashSet<T>,
+}
+
+impl<T, N, F> Traversal<T, N, F>
+where
+    T: Eq + Hash + Clone,
+    N: IntoIterator<Item = T>,
+    F: FnMut(&T) -> N,
+{
+    fn new(start: T, order: TraversalOrder, successors: F) -> Self {
+        let mut queued = HashSet::new();
+        queued.insert(start.clone());
+
+        Self {
+            inner: UnvisitedIterator::from_value(start),
+            order,
+            successors,
+            queued,
+        }
+    }
+}
+
+impl<T, N, F> Iterator for Traversal<T, N, F>
+where
+    T: Eq + Hash + Clone,
+    N: IntoIterator<Item = T>,
+    F: FnMut(&T) -> N,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.inner.next()?;
+
+        for successor in (self.successors)(&node) {
+            if self.inner.visited.contains(&successor) || self.queued.contains(&successor) {
+                continue;
+            }
+
+            self.queued.insert(successor.clone());
+            match self.order {
+                TraversalOrder::Bfs => self.inner.push_back(successor),
+                TraversalOrder::Dfs => self.inner.push_front(successor),
+            }
+        }
+
+        Some(node)
+    }
+}
+
+impl<T: Eq + Hash + Clone> UnvisitedIterator<T> {
+    pub fn traverse<N, F>(start: T, order: TraversalOrder, successors: F) -> Traversal<T, N, F>
+    where
+        N: IntoIterator<Item = T>,
+        F: FnMut(&T) -> N,
+    {
+        Traversal::new(start, order, successors)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DfsEvent<T> {
+    Finished(T),
+    BackEdge(T, T),
+}
+
+pub struct TopologicalDfs<T, N, F>
+where
+    T: Eq + Hash + Clone,
+    N: IntoIterator<Item = T>,
+    F: FnMut(&T) -> N,
+{
+    // Finished (black) nodes.
+    black: HashSet<T>,
+    // Nodes on the current DFS stack (grey).
+    grey: HashSet<T>,
+    stack: Vec<(T, <N as IntoIterator>::IntoIter)>,
+    successors: F,
+}
+
+impl<T, N, F> TopologicalDfs<T, N, F>
+where
+    T: Eq + Hash + Clone,
+    N: IntoIterator<Item = T>,
+    F: FnMut(&T) -> N,
+{
+    fn new(start: T, mut successors: F) -> Self {
+        let successor_iter = successors(&start).into_iter();
+        let mut grey = HashSet::new();
+        grey.insert(start.clone());
+
+        Self {
+            black: HashSet::new(),
+            grey,
+            stack: vec![(start, successor_iter)],
+            successors,
+        }
+    }
+}
+
+impl<T, N, F> Iterator for TopologicalDfs<T, N, F>
+where
+    T: Eq + Hash + Clone,
+    N: IntoIterator<Item = T>,
+    F: FnMut(&T) -> N,
+{
+    type Item = DfsEvent<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (node, successors) = self.stack.last_mut()?;
+
+            match successors.next() {
+                Some(successor) if self.black.contains(&successor) => continue,
+                Some(successor) if self.grey.contains(&successor) => {
+                    return Some(DfsEvent::BackEdge(node.clone(), successor));
+                }
+                Some(successor) => {
+                    self.grey.insert(successor.clone());
+                    let successor_iter = (self.successors)(&successor).into_iter();
+                    self.stack.push((successor, successor_iter));
+                }
+                None => {
+                    let (node, _) = self.stack.pop().unwrap();
+                    self.grey.remove(&node);
+                    self.black.insert(node.clone());
+                    return Some(DfsEvent::Finished(node));
+                }
+            }
+        }
+    }
+}
+
+impl<T: Eq + Hash + Clone> UnvisitedIterator<T> {
+    pub fn topological_dfs<N, F>(start: T, successors: F) -> TopologicalDfs<T, N, F>
+    where
+        N: IntoIterator<Item = T>,
+        F: FnMut(&T) -> N,
+    {
+        TopologicalDfs::new(start, successors)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -118,4 +430,246 @@ mod tests {
         iter.push_back(2);
         assert_eq!(iter.inner.back(), Some(&2));
     }
+
+    fn small_graph(node: &u32) -> Vec<u32> {
+        match node {
+            1 => vec![2, 3],
+            2 => vec![4],
+            3 => vec![4],
+            4 => vec![1],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn traverse_bfs_visits_each_node_once_in_breadth_first_order() {
+        let visited: Vec<_> =
+            UnvisitedIterator::traverse(1, TraversalOrder::Bfs, small_graph).collect();
+        assert_eq!(visited, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn traverse_dfs_visits_each_node_once_in_depth_first_order() {
+        let visited: Vec<_> =
+            UnvisitedIterator::traverse(1, TraversalOrder::Dfs, small_graph).collect();
+        assert_eq!(visited, vec![1, 3, 4, 2]);
+    }
+
+    #[test]
+    fn traverse_does_not_enqueue_a_high_fan_in_successor_more_than_once() {
+        let mut traversal = UnvisitedIterator::traverse(1, TraversalOrder::Bfs, small_graph);
+        traversal.next(); // yields 1, enqueues 2 and 3
+        traversal.next(); // yields 2, enqueues 4
+        traversal.next(); // yields 3, 4 is already queued and must not be enqueued again
+
+        assert_eq!(traversal.inner.inner, VecDeque::from(vec![4]));
+    }
+
+    fn dag(node: &u32) -> Vec<u32> {
+        match node {
+            1 => vec![2, 3],
+            2 => vec![4],
+            3 => vec![4],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn topological_dfs_yields_finished_nodes_in_post_order() {
+        let events: Vec<_> = UnvisitedIterator::topological_dfs(1, dag).collect();
+        assert_eq!(
+            events,
+            vec![
+                DfsEvent::Finished(4),
+                DfsEvent::Finished(2),
+                DfsEvent::Finished(3),
+                DfsEvent::Finished(1),
+            ]
+        );
+
+        let topological_order: Vec<_> = events
+            .into_iter()
+            .filter_map(|event| match event {
+                DfsEvent::Finished(node) => Some(node),
+                DfsEvent::BackEdge(..) => None,
+            })
+            .rev()
+            .collect();
+        assert_eq!(topological_order, vec![1, 3, 2, 4]);
+    }
+
+    #[test]
+    fn topological_dfs_reports_back_edges_on_cycles() {
+        let events: Vec<_> =
+            UnvisitedIterator::topological_dfs(1, |node| match node {
+                1 => vec![2],
+                2 => vec![1],
+                _ => vec![],
+            })
+            .collect();
+
+        assert_eq!(
+            events,
+            vec![
+                DfsEvent::BackEdge(2, 1),
+                DfsEvent::Finished(2),
+                DfsEvent::Finished(1),
+            ]
+        );
+    }
+
+    #[derive(Default)]
+    struct IdentityHasher(u64);
+
+    impl std::hash::Hasher for IdentityHasher {
+        fn finish(&self) -> u64 {
+            self.0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            for byte in bytes {
+                self.0 = self.0.wrapping_shl(8) | u64::from(*byte);
+            }
+        }
+    }
+
+    #[derive(Default, Clone)]
+    struct IdentityBuildHasher;
+
+    impl BuildHasher for IdentityBuildHasher {
+        type Hasher = IdentityHasher;
+
+        fn build_hasher(&self) -> Self::Hasher {
+            IdentityHasher::default()
+        }
+    }
+
+    #[test]
+    fn with_hasher_uses_the_supplied_hasher() {
+        let mut iter = UnvisitedIterator::with_hasher(1, IdentityBuildHasher);
+        iter.push_back(2);
+        iter.push_back(1);
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn from_iter_with_hasher_dedups_like_the_default_hasher() {
+        let mut iter =
+            UnvisitedIterator::from_iter_with_hasher(vec![1, 2, 1, 3].into_iter(), IdentityBuildHasher);
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.next(), Some(3));
+        assert_eq!(iter.next(), None);
+    }
+
+    struct Node {
+        id: u32,
+        payload: &'static str,
+    }
+
+    #[test]
+    fn skip_visited_by_key_dedups_by_the_projected_key() {
+        let nodes = vec![
+            Node { id: 1, payload: "a" },
+            Node { id: 2, payload: "b" },
+            Node { id: 1, payload: "stale-a" },
+            Node { id: 3, payload: "c" },
+        ];
+
+        let mut iter = nodes.into_iter().skip_visited_by_key(|node| &node.id);
+        assert_eq!(iter.next().map(|node| node.payload), Some("a"));
+        assert_eq!(iter.next().map(|node| node.payload), Some("b"));
+        assert_eq!(iter.next().map(|node| node.payload), Some("c"));
+        assert_eq!(iter.next().map(|node| node.payload), None);
+    }
+
+    #[test]
+    fn unvisited_by_key_iterator_supports_push_front_and_push_back() {
+        let mut iter = UnvisitedByKeyIterator::from_iter(
+            vec![Node { id: 1, payload: "a" }].into_iter(),
+            |node: &Node| &node.id,
+        );
+        iter.push_back(Node { id: 2, payload: "b" });
+        iter.push_front(Node { id: 3, payload: "c" });
+
+        assert_eq!(iter.next().map(|node| node.payload), Some("c"));
+        assert_eq!(iter.next().map(|node| node.payload), Some("a"));
+        assert_eq!(iter.next().map(|node| node.payload), Some("b"));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn with_visited_skips_the_seeded_nodes() {
+        let mut visited = HashSet::new();
+        visited.insert(2);
+
+        let mut iter = UnvisitedIterator::with_visited(visited, vec![1, 2, 3].into_iter());
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next(), Some(3));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn visited_exposes_the_nodes_already_yielded() {
+        let mut iter = UnvisitedIterator::from_iter(vec![1, 2].into_iter());
+        iter.next();
+        assert!(iter.visited().contains(&1));
+        assert!(!iter.visited().contains(&2));
+    }
+
+    #[test]
+    fn into_parts_returns_the_visited_set_and_remaining_frontier() {
+        let mut iter = UnvisitedIterator::from_iter(vec![1, 2, 3].into_iter());
+        iter.next();
+
+        let (visited, frontier) = iter.into_parts();
+        assert!(visited.contains(&1));
+        assert_eq!(frontier, VecDeque::from(vec![2, 3]));
+    }
+
+    #[test]
+    fn bitor_unions_the_visited_sets_of_two_iterators() {
+        let mut a = UnvisitedIterator::from_iter(vec![1, 2].into_iter());
+        a.next();
+        let mut b = UnvisitedIterator::from_iter(vec![2, 3].into_iter());
+        b.next();
+
+        let merged = &a | &b;
+        assert_eq!(merged, HashSet::from([1, 2]));
+    }
+
+    #[test]
+    fn sub_excludes_the_visited_set_of_the_other_iterator() {
+        let mut a = UnvisitedIterator::from_iter(vec![1, 2].into_iter());
+        a.next();
+        a.next();
+        let mut blocklist = UnvisitedIterator::from_iter(vec![2].into_iter());
+        blocklist.next();
+
+        let remaining = &a - &blocklist;
+        assert_eq!(remaining, HashSet::from([1]));
+    }
+
+    #[test]
+    fn size_hint_upper_bounds_by_the_remaining_frontier_len() {
+        let mut iter = UnvisitedIterator::from_iter(vec![1, 2, 1, 3].into_iter());
+        assert_eq!(iter.size_hint(), (0, Some(4)));
+        iter.next();
+        assert_eq!(iter.size_hint(), (0, Some(3)));
+    }
+
+    #[test]
+    fn into_visited_drains_the_frontier_into_the_visited_set() {
+        let iter = UnvisitedIterator::from_iter(vec![1, 2, 1, 3].into_iter());
+        assert_eq!(iter.into_visited(), HashSet::from([1, 2, 3]));
+    }
+
+    #[test]
+    fn into_visited_keeps_elements_already_yielded() {
+        let mut iter = UnvisitedIterator::from_iter(vec![1, 2, 3].into_iter());
+        iter.next();
+        assert_eq!(iter.into_visited(), HashSet::from([1, 2, 3]));
+    }
 }